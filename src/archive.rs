@@ -0,0 +1,185 @@
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use tar::Archive;
+
+use crate::listable::Listable;
+
+/// One member of a tar archive, mapped onto the same fields `Item` exposes
+/// so it can flow through the normal long-format/grid rendering.
+struct ArchiveEntry {
+    name: String,
+    size: u64,
+    mtime: u64,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    is_dir: bool,
+    is_symlink: bool,
+    link_target: Option<String>,
+}
+
+impl Listable for ArchiveEntry {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn len(&self) -> u64 {
+        self.size
+    }
+
+    fn mtime_secs(&self) -> u64 {
+        self.mtime
+    }
+
+    fn mode(&self) -> u32 {
+        self.mode & 0o7777
+    }
+
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    fn symlink_target(&self) -> Option<String> {
+        self.link_target.clone()
+    }
+}
+
+/// Whether `path` looks like something this module knows how to browse:
+/// `.tar`, `.tar.gz` or `.tgz`.
+pub fn is_tar_path(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_lowercase();
+    name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+fn is_gzip(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_lowercase();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// List the members of the tar archive at `path` as `Listable` entries.
+pub fn list_archive(path: &Path) -> io::Result<Vec<Box<dyn Listable>>> {
+    let file = File::open(path)?;
+
+    let entries = if is_gzip(path) {
+        read_entries(Archive::new(GzDecoder::new(file)))?
+    } else {
+        read_entries(Archive::new(file))?
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|e| Box::new(e) as Box<dyn Listable>)
+        .collect())
+}
+
+fn read_entries<R: Read>(mut archive: Archive<R>) -> io::Result<Vec<ArchiveEntry>> {
+    let mut out = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let header = entry.header();
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let link_target = entry
+            .link_name()
+            .ok()
+            .flatten()
+            .map(|p| p.to_string_lossy().into_owned());
+
+        out.push(ArchiveEntry {
+            name,
+            size: header.size().unwrap_or(0),
+            mtime: header.mtime().unwrap_or(0),
+            mode: header.mode().unwrap_or(0),
+            uid: header.uid().unwrap_or(0) as u32,
+            gid: header.gid().unwrap_or(0) as u32,
+            is_dir: header.entry_type().is_dir(),
+            is_symlink: header.entry_type().is_symlink(),
+            link_target,
+        });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::fs;
+    use tar::{Builder, Header};
+
+    #[test]
+    fn is_tar_path_matches_known_extensions_case_insensitively() {
+        assert!(is_tar_path(Path::new("backup.tar")));
+        assert!(is_tar_path(Path::new("backup.TAR")));
+        assert!(is_tar_path(Path::new("backup.tar.gz")));
+        assert!(is_tar_path(Path::new("backup.tgz")));
+    }
+
+    #[test]
+    fn is_tar_path_rejects_unrelated_extensions() {
+        assert!(!is_tar_path(Path::new("backup.zip")));
+        assert!(!is_tar_path(Path::new("tarball")));
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ruls-archive-test-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn list_archive_maps_a_plain_tar() {
+        let path = temp_path("plain.tar");
+        let file = fs::File::create(&path).unwrap();
+        let mut builder = Builder::new(file);
+
+        let mut header = Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o100644);
+        header.set_cksum();
+        builder.append_data(&mut header, "hello.txt", b"world" as &[u8]).unwrap();
+        builder.into_inner().unwrap();
+
+        let entries = list_archive(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name(), "hello.txt");
+        assert_eq!(entries[0].len(), 5);
+        assert!(!entries[0].is_dir());
+    }
+
+    #[test]
+    fn list_archive_decompresses_a_gzipped_tar() {
+        let path = temp_path("gzipped.tar.gz");
+        let file = fs::File::create(&path).unwrap();
+        let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+
+        let mut header = Header::new_gnu();
+        header.set_size(0);
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_mode(0o040755);
+        header.set_cksum();
+        builder.append_data(&mut header, "a_dir", io::empty()).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let entries = list_archive(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name(), "a_dir");
+        assert!(entries[0].is_dir());
+    }
+}
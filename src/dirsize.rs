@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Recursive (`du`-style) byte totals for directories, computed with a
+/// single `WalkDir` pass per listed root and cached by canonical path so
+/// `-S` sorting and the size column don't re-walk a subtree that's already
+/// been summed.
+#[derive(Default)]
+pub struct DirSizeCache {
+    sums: RefCell<HashMap<PathBuf, u64>>,
+    computed_roots: RefCell<HashSet<PathBuf>>,
+}
+
+impl DirSizeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Walk `root` and accumulate sizes into every ancestor directory down
+    /// to (and including) `root`, unless an ancestor of `root` has already
+    /// been walked.
+    pub fn ensure(&self, root: &Path) {
+        let Ok(root) = root.canonicalize() else {
+            return;
+        };
+
+        if self
+            .computed_roots
+            .borrow()
+            .iter()
+            .any(|done| root.starts_with(done))
+        {
+            return;
+        }
+
+        let mut sums = HashMap::new();
+        for entry in WalkDir::new(&root)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+        {
+            if entry.file_type().is_dir() {
+                sums.entry(entry.path().to_path_buf()).or_insert(0);
+                continue;
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(len) = entry.metadata().map(|m| m.len()) else {
+                continue;
+            };
+
+            let mut dir = entry.path().parent();
+            while let Some(d) = dir {
+                *sums.entry(d.to_path_buf()).or_insert(0) += len;
+                if d == root {
+                    break;
+                }
+                dir = d.parent();
+            }
+        }
+
+        self.sums.borrow_mut().extend(sums);
+        self.computed_roots.borrow_mut().insert(root);
+    }
+
+    /// Aggregated size for a directory previously covered by `ensure`, or
+    /// `None` if it hasn't been walked.
+    pub fn size_of(&self, path: &Path) -> Option<u64> {
+        let canonical = path.canonicalize().ok()?;
+        self.sums.borrow().get(&canonical).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("ruls-dirsize-test-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("sub")).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn sums_file_sizes_recursively() {
+        let tmp = TempDir::new("sums");
+        fs::write(tmp.0.join("a.txt"), b"hello").unwrap(); // 5 bytes
+        fs::write(tmp.0.join("sub/b.txt"), b"hi").unwrap(); // 2 bytes
+
+        let cache = DirSizeCache::new();
+        cache.ensure(&tmp.0);
+
+        assert_eq!(cache.size_of(&tmp.0), Some(7));
+        assert_eq!(cache.size_of(&tmp.0.join("sub")), Some(2));
+    }
+
+    #[test]
+    fn unwalked_directory_has_no_size() {
+        let tmp = TempDir::new("unwalked");
+        let cache = DirSizeCache::new();
+        assert_eq!(cache.size_of(&tmp.0), None);
+    }
+
+    #[test]
+    fn does_not_rewalk_a_directory_already_covered_by_an_ancestor() {
+        let tmp = TempDir::new("rewalk");
+        fs::write(tmp.0.join("a.txt"), b"hello").unwrap();
+
+        let cache = DirSizeCache::new();
+        cache.ensure(&tmp.0);
+        // Add a file after the walk; re-`ensure`-ing the already-covered
+        // subdirectory must be a no-op, so the total stays stale at 5.
+        fs::write(tmp.0.join("sub/late.txt"), b"later").unwrap();
+        cache.ensure(&tmp.0.join("sub"));
+
+        assert_eq!(cache.size_of(&tmp.0), Some(5));
+    }
+}
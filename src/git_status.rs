@@ -0,0 +1,204 @@
+use git2::{Repository, Status, StatusOptions};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Per-path index/worktree status, e.g. `('M', '-')` for a staged-only
+/// modification.
+pub type StatusCode = (char, char);
+
+struct RepoCache {
+    workdir: PathBuf,
+    statuses: HashMap<PathBuf, Status>,
+}
+
+/// Lazily discovers the enclosing git repository and caches its status map
+/// so a recursive `-R` listing only walks the repo and runs `git status`
+/// once, regardless of how many subdirectories get listed. Also remembers
+/// the last directory that turned out to have no enclosing repo at all, so
+/// plain `-l` listings of a non-git directory don't re-run
+/// `Repository::discover` for every single entry.
+#[derive(Default)]
+pub struct GitContext {
+    cache: RefCell<Option<RepoCache>>,
+    no_repo_dir: RefCell<Option<PathBuf>>,
+}
+
+impl GitContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status code for `path`, or `None` when it isn't inside a git working
+    /// tree (or the repo/status lookup failed for any reason). For a
+    /// directory this is the status rolled up from everything inside it
+    /// (see `load`), not just the directory path itself.
+    pub fn status_for(&self, path: &Path) -> Option<StatusCode> {
+        self.ensure_repo_for(path);
+
+        let cache = self.cache.borrow();
+        let repo_cache = cache.as_ref()?;
+        let canonical = path.canonicalize().ok()?;
+        let rel = canonical.strip_prefix(&repo_cache.workdir).ok()?;
+        let status = repo_cache
+            .statuses
+            .get(rel)
+            .copied()
+            .unwrap_or(Status::CURRENT);
+        Some(status_chars(status))
+    }
+
+    fn ensure_repo_for(&self, path: &Path) {
+        let Ok(canonical) = path.canonicalize() else {
+            return;
+        };
+
+        if let Some(rc) = self.cache.borrow().as_ref() {
+            if canonical.starts_with(&rc.workdir) {
+                return;
+            }
+        }
+
+        let query_dir = if canonical.is_dir() {
+            canonical.clone()
+        } else {
+            canonical
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| canonical.clone())
+        };
+
+        if self.no_repo_dir.borrow().as_deref() == Some(query_dir.as_path()) {
+            return;
+        }
+
+        let Some(repo_cache) = Self::load(path) else {
+            *self.no_repo_dir.borrow_mut() = Some(query_dir);
+            return;
+        };
+        *self.cache.borrow_mut() = Some(repo_cache);
+        *self.no_repo_dir.borrow_mut() = None;
+    }
+
+    fn load(path: &Path) -> Option<RepoCache> {
+        let repo = Repository::discover(path).ok()?;
+        let workdir = repo.workdir()?.to_path_buf();
+
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .include_ignored(true)
+            .recurse_untracked_dirs(true);
+        let statuses = repo.statuses(Some(&mut opts)).ok()?;
+
+        // git2 only ever reports statuses for the paths that actually
+        // changed (files, or a whole directory when it's ignored/untracked
+        // as a unit) - never for every ancestor directory above them. Roll
+        // each entry's status up into every ancestor so a directory's entry
+        // reflects everything changed inside it, the same way
+        // `DirSizeCache::ensure` accumulates file sizes into ancestors.
+        let mut map: HashMap<PathBuf, Status> = HashMap::new();
+        for entry in statuses.iter() {
+            let Some(p) = entry.path() else { continue };
+            let status = entry.status();
+            let rel = PathBuf::from(p.trim_end_matches('/'));
+
+            *map.entry(rel.clone()).or_insert(Status::empty()) |= status;
+
+            let mut dir = rel.parent();
+            while let Some(d) = dir {
+                if d.as_os_str().is_empty() {
+                    break;
+                }
+                *map.entry(d.to_path_buf()).or_insert(Status::empty()) |= status;
+                dir = d.parent();
+            }
+        }
+
+        Some(RepoCache {
+            workdir,
+            statuses: map,
+        })
+    }
+}
+
+fn status_chars(status: Status) -> StatusCode {
+    let index = if status.intersects(Status::INDEX_NEW) {
+        'A'
+    } else if status.intersects(Status::INDEX_MODIFIED | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE) {
+        'M'
+    } else if status.intersects(Status::INDEX_DELETED) {
+        'D'
+    } else {
+        '-'
+    };
+
+    let worktree = if status.intersects(Status::IGNORED) {
+        'I'
+    } else if status.intersects(Status::WT_NEW) {
+        '?'
+    } else if status.intersects(Status::WT_MODIFIED | Status::WT_RENAMED | Status::WT_TYPECHANGE) {
+        'M'
+    } else if status.intersects(Status::WT_DELETED) {
+        'D'
+    } else {
+        '-'
+    };
+
+    (index, worktree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    struct TempRepo(PathBuf);
+
+    impl TempRepo {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("ruls-git-status-test-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("sub")).unwrap();
+            Repository::init(&dir).unwrap();
+            TempRepo(dir)
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn status_chars_classifies_index_and_worktree_bits_independently() {
+        assert_eq!(status_chars(Status::CURRENT), ('-', '-'));
+        assert_eq!(status_chars(Status::INDEX_NEW), ('A', '-'));
+        assert_eq!(status_chars(Status::WT_NEW), ('-', '?'));
+        assert_eq!(
+            status_chars(Status::INDEX_MODIFIED | Status::WT_MODIFIED),
+            ('M', 'M')
+        );
+        assert_eq!(status_chars(Status::IGNORED), ('-', 'I'));
+    }
+
+    #[test]
+    fn untracked_file_in_a_subdirectory_marks_the_subdirectory_too() {
+        let repo = TempRepo::new("rollup");
+        fs::write(repo.0.join("sub/new.txt"), b"hi").unwrap();
+
+        let ctx = GitContext::new();
+        assert_eq!(ctx.status_for(&repo.0.join("sub/new.txt")), Some(('-', '?')));
+        // The directory itself has no status entry of its own in git2, but
+        // should roll up the untracked status of the file inside it.
+        assert_eq!(ctx.status_for(&repo.0.join("sub")), Some(('-', '?')));
+    }
+
+    #[test]
+    fn clean_directory_reports_current() {
+        let repo = TempRepo::new("clean");
+
+        let ctx = GitContext::new();
+        assert_eq!(ctx.status_for(&repo.0), Some(('-', '-')));
+    }
+}
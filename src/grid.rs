@@ -0,0 +1,128 @@
+use unicode_width::UnicodeWidthStr;
+
+/// Columns of space between adjacent entries in the grid.
+const GUTTER: usize = 2;
+
+/// One name ready to be laid out in a grid: the string to print (which may
+/// carry ANSI color codes) alongside its real on-screen width.
+pub struct Cell {
+    pub text: String,
+    pub width: usize,
+}
+
+impl Cell {
+    pub fn new(styled: String, plain: &str) -> Self {
+        Cell {
+            text: styled,
+            width: plain.width(),
+        }
+    }
+}
+
+/// Terminal width in columns, falling back to 80 when stdout isn't a tty
+/// (or the ioctl fails, e.g. when redirected to a file).
+pub fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(w), _)| w as usize)
+        .filter(|&w| w > 0)
+        .unwrap_or(80)
+}
+
+/// Lay `cells` out in as many columns as fit in `term_width`, printing one
+/// line per row. `row_major` selects `-x` fill order (left-to-right, top-to-
+/// bottom); the default is `ls`'s column-major order (top-to-bottom, then
+/// across).
+pub fn print_grid(cells: &[Cell], term_width: usize, row_major: bool) {
+    if cells.is_empty() {
+        return;
+    }
+
+    let cols = fit_columns(cells, term_width, row_major);
+    let rows = cells.len().div_ceil(cols);
+    let widths = column_widths(cells, cols, rows, row_major);
+
+    for row in 0..rows {
+        let mut line = String::new();
+        for (col, col_width) in widths.iter().enumerate() {
+            let idx = if row_major {
+                row * cols + col
+            } else {
+                col * rows + row
+            };
+            let Some(cell) = cells.get(idx) else {
+                continue;
+            };
+            line.push_str(&cell.text);
+            if col + 1 < cols {
+                let pad = col_width - cell.width + GUTTER;
+                line.push_str(&" ".repeat(pad));
+            }
+        }
+        println!("{line}");
+    }
+}
+
+/// Widest number of columns whose combined width (names plus a
+/// `GUTTER`-wide gap between them) still fits in `term_width`, falling
+/// back to a single column when even that doesn't fit.
+fn fit_columns(cells: &[Cell], term_width: usize, row_major: bool) -> usize {
+    let n = cells.len();
+
+    for candidate in (1..=n).rev() {
+        let rows = n.div_ceil(candidate);
+        let widths = column_widths(cells, candidate, rows, row_major);
+        let total: usize = widths.iter().sum::<usize>() + GUTTER * widths.len().saturating_sub(1);
+        if total <= term_width {
+            return candidate;
+        }
+    }
+    1
+}
+
+fn column_widths(cells: &[Cell], cols: usize, rows: usize, row_major: bool) -> Vec<usize> {
+    let mut widths = vec![0usize; cols];
+    for (i, cell) in cells.iter().enumerate() {
+        let col = if row_major { i % cols } else { i / rows };
+        if cell.width > widths[col] {
+            widths[col] = cell.width;
+        }
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(names: &[&str]) -> Vec<Cell> {
+        names.iter().map(|n| Cell::new(n.to_string(), n)).collect()
+    }
+
+    #[test]
+    fn fits_everything_on_one_line_when_it_all_fits() {
+        let cells = cells(&["a", "bb", "ccc"]);
+        assert_eq!(fit_columns(&cells, 80, false), 3);
+    }
+
+    #[test]
+    fn falls_back_to_one_column_per_line_when_nothing_fits() {
+        let cells = cells(&["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc"]);
+        assert_eq!(fit_columns(&cells, 5, false), 1);
+    }
+
+    #[test]
+    fn picks_the_widest_grid_that_still_fits() {
+        // Four 1-wide names plus a 2-col gutter: 4 cols need
+        // 1+2+1+2+1+2+1=10; 3 cols take 2 rows in column-major order, which
+        // leaves the third column empty (width 0), so they need 1+2+1+2+0=6.
+        let cells = cells(&["a", "b", "c", "d"]);
+        assert_eq!(fit_columns(&cells, 6, false), 3);
+    }
+
+    #[test]
+    fn column_widths_uses_widest_cell_per_column() {
+        let cells = cells(&["a", "bb", "c", "dddd"]);
+        // column-major, 2 cols, 2 rows -> col 0 = [a, bb], col 1 = [c, dddd]
+        assert_eq!(column_widths(&cells, 2, 2, false), vec![2, 4]);
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+/// Coarse file-type classification, independent of the permission bits
+/// `mode()` exposes. Used to pick an `LS_COLORS` type key (`di`, `pi`,
+/// `so`, ...); most entries are plain files, directories or symlinks, so
+/// those are checked via `is_dir`/`is_symlink` and this only needs to add
+/// the device/fifo/socket cases `mode()` can't.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+/// Something `print_items` can render: either a real filesystem entry
+/// (`Item`) or a member of an archive being browsed in place (see
+/// `archive.rs`). Keeping this minimal lets both flow through the same
+/// sorting, coloring and long-format code.
+pub trait Listable {
+    fn name(&self) -> String;
+    fn len(&self) -> u64;
+    fn mtime_secs(&self) -> u64;
+    /// Permission bits only (no file-type bits), as in `st_mode & 0o7777`.
+    fn mode(&self) -> u32;
+    fn is_dir(&self) -> bool;
+    fn is_symlink(&self) -> bool;
+    fn uid(&self) -> u32;
+    fn gid(&self) -> u32;
+
+    fn nlink(&self) -> u64 {
+        1
+    }
+
+    fn symlink_target(&self) -> Option<String> {
+        None
+    }
+
+    /// Real filesystem path, for entries that actually exist on disk. Used
+    /// for git status and broken-symlink detection; `None` for archive
+    /// members, which have neither.
+    fn real_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// File-type classification for `LS_COLORS` lookups beyond plain
+    /// file/dir/symlink. Archive members (and anything else that can't
+    /// know better) fall back to `File`/`Directory`/`Symlink`.
+    fn file_kind(&self) -> FileKind {
+        if self.is_symlink() {
+            FileKind::Symlink
+        } else if self.is_dir() {
+            FileKind::Directory
+        } else {
+            FileKind::File
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use crate::listable::{FileKind, Listable};
+
+/// Built-in palette used when `LS_COLORS` isn't set, matching the scheme
+/// `colorize_name` used before this module existed.
+const DEFAULT_DIR: &str = "01;34";
+const DEFAULT_LINK: &str = "01;36";
+const DEFAULT_EXEC: &str = "01;32";
+const DEFAULT_ORPHAN: &str = "01;31";
+
+/// Parsed `LS_COLORS` (or built-in fallback): SGR parameters keyed by file
+/// type (`di`, `ln`, `ex`, ...) and by lowercased file extension (`tar`,
+/// `gz`, ...), looked up without the leading `*.`.
+pub struct LsColors {
+    by_type: HashMap<String, String>,
+    by_ext: HashMap<String, String>,
+    /// Dot-less glob entries (e.g. `*~`, `*#`) matched against the full
+    /// file name's suffix rather than through `extension_of`, since names
+    /// like `backup~` have no `.` for `extension_of` to find.
+    by_suffix: Vec<(String, String)>,
+}
+
+impl LsColors {
+    /// Read `LS_COLORS` from the environment, falling back to the BSD/macOS
+    /// `LSCOLORS` positional format, then to the built-in scheme when
+    /// neither is set.
+    pub fn from_env() -> Self {
+        if let Ok(spec) = env::var("LS_COLORS") {
+            if !spec.is_empty() {
+                return Self::parse(&spec);
+            }
+        }
+        if let Ok(spec) = env::var("LSCOLORS") {
+            if !spec.is_empty() {
+                return Self::parse_lscolors(&spec);
+            }
+        }
+        Self::default_scheme()
+    }
+
+    fn default_scheme() -> Self {
+        let mut by_type = HashMap::new();
+        by_type.insert("di".to_string(), DEFAULT_DIR.to_string());
+        by_type.insert("ln".to_string(), DEFAULT_LINK.to_string());
+        by_type.insert("ex".to_string(), DEFAULT_EXEC.to_string());
+        by_type.insert("or".to_string(), DEFAULT_ORPHAN.to_string());
+        LsColors {
+            by_type,
+            by_ext: HashMap::new(),
+            by_suffix: Vec::new(),
+        }
+    }
+
+    fn parse(spec: &str) -> Self {
+        let mut by_type = HashMap::new();
+        let mut by_ext = HashMap::new();
+        let mut by_suffix = Vec::new();
+
+        for entry in spec.split(':') {
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            if value.is_empty() {
+                continue;
+            }
+
+            if let Some(ext) = key.strip_prefix("*.") {
+                by_ext.insert(ext.to_lowercase(), value.to_string());
+            } else if let Some(glob) = key.strip_prefix('*') {
+                // Glob without a dot (e.g. `*~`, `*#`); match against the
+                // whole file name's suffix, not through `extension_of`.
+                by_suffix.push((glob.to_lowercase(), value.to_string()));
+            } else {
+                by_type.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        LsColors {
+            by_type,
+            by_ext,
+            by_suffix,
+        }
+    }
+
+    /// Parse the BSD/macOS `LSCOLORS` format: 11 fixed two-letter
+    /// foreground/background pairs (directory, symlink, socket, pipe,
+    /// executable, block special, character special, setuid/setgid
+    /// executable, sticky/other-writable directory), with `x` meaning
+    /// "use the terminal default". Unlike `LS_COLORS` there's no extension
+    /// table, and only the first seven slots are read: the ones this
+    /// codebase has no type key for (setuid/setgid executables,
+    /// other-writable directories) are ignored.
+    fn parse_lscolors(spec: &str) -> Self {
+        const SLOTS: [&str; 7] = ["di", "ln", "so", "pi", "ex", "bd", "cd"];
+
+        let chars: Vec<char> = spec.chars().collect();
+        let mut by_type = HashMap::new();
+        by_type.insert("or".to_string(), DEFAULT_ORPHAN.to_string());
+
+        for (i, key) in SLOTS.iter().enumerate() {
+            let fg = chars.get(i * 2).copied().unwrap_or('x');
+            let bg = chars.get(i * 2 + 1).copied().unwrap_or('x');
+            if let Some(params) = lscolors_pair_to_sgr(fg, bg) {
+                by_type.insert(key.to_string(), params);
+            }
+        }
+
+        // `LSCOLORS` has no orphan-symlink slot and may leave di/ln/ex at
+        // "default", so fall back to the built-in scheme for those rather
+        // than leaving them unstyled.
+        by_type
+            .entry("di".to_string())
+            .or_insert_with(|| DEFAULT_DIR.to_string());
+        by_type
+            .entry("ln".to_string())
+            .or_insert_with(|| DEFAULT_LINK.to_string());
+        by_type
+            .entry("ex".to_string())
+            .or_insert_with(|| DEFAULT_EXEC.to_string());
+
+        LsColors {
+            by_type,
+            by_ext: HashMap::new(),
+            by_suffix: Vec::new(),
+        }
+    }
+
+    /// SGR parameter string for `item`, or `None` if nothing matches (plain
+    /// file with no extension rule).
+    pub fn style_for(&self, item: &dyn Listable, name: &str) -> Option<&str> {
+        if let Some(ext) = extension_of(name) {
+            if let Some(params) = self.by_ext.get(&ext.to_lowercase()) {
+                return Some(params);
+            }
+        }
+
+        let lower_name = name.to_lowercase();
+        for (suffix, params) in &self.by_suffix {
+            if lower_name.ends_with(suffix.as_str()) {
+                return Some(params);
+            }
+        }
+
+        let type_key = self.type_key(item);
+        self.by_type.get(type_key).map(|s| s.as_str())
+    }
+
+    fn type_key(&self, item: &dyn Listable) -> &'static str {
+        match item.file_kind() {
+            FileKind::Symlink if is_broken_symlink(item) => "or",
+            FileKind::Symlink => "ln",
+            FileKind::Directory => "di",
+            FileKind::Fifo => "pi",
+            FileKind::Socket => "so",
+            FileKind::BlockDevice => "bd",
+            FileKind::CharDevice => "cd",
+            FileKind::File if crate::is_executable_bits(item.mode(), item.is_dir()) => "ex",
+            FileKind::File => "fi",
+        }
+    }
+}
+
+fn extension_of(name: &str) -> Option<&str> {
+    let dot = name.rfind('.')?;
+    if dot == 0 {
+        return None;
+    }
+    Some(&name[dot + 1..])
+}
+
+fn is_broken_symlink(item: &dyn Listable) -> bool {
+    match item.real_path() {
+        Some(path) => fs::metadata(path).is_err(),
+        None => false,
+    }
+}
+
+/// SGR params for one `LSCOLORS` foreground/background pair, or `None` when
+/// both letters are `x` (terminal default, i.e. no override).
+fn lscolors_pair_to_sgr(fg: char, bg: char) -> Option<String> {
+    let mut params = Vec::new();
+    if let Some((code, bold)) = lscolors_fg_code(fg) {
+        if bold {
+            params.push("01".to_string());
+        }
+        params.push(code.to_string());
+    }
+    if let Some(code) = lscolors_bg_code(bg) {
+        params.push(code.to_string());
+    }
+
+    if params.is_empty() {
+        None
+    } else {
+        Some(params.join(";"))
+    }
+}
+
+/// `LSCOLORS` foreground letter to (SGR code, bold). Uppercase selects the
+/// bold/light variant of the same color; `x` means "no override".
+fn lscolors_fg_code(c: char) -> Option<(u8, bool)> {
+    if c == 'x' {
+        return None;
+    }
+    let bold = c.is_ascii_uppercase();
+    let code = match c.to_ascii_lowercase() {
+        'a' => 30,
+        'b' => 31,
+        'c' => 32,
+        'd' => 33,
+        'e' => 34,
+        'f' => 35,
+        'g' => 36,
+        'h' => 37,
+        _ => return None,
+    };
+    Some((code, bold))
+}
+
+/// `LSCOLORS` background letter to SGR code. Backgrounds have no bold
+/// variant, so the letter's case is ignored.
+fn lscolors_bg_code(c: char) -> Option<u8> {
+    match c.to_ascii_lowercase() {
+        'a' => Some(40),
+        'b' => Some(41),
+        'c' => Some(42),
+        'd' => Some(43),
+        'e' => Some(44),
+        'f' => Some(45),
+        'g' => Some(46),
+        'h' => Some(47),
+        _ => None,
+    }
+}
+
+
+/// Wrap `name` in the raw SGR params, e.g. `\x1b[01;34mNAME\x1b[0m`.
+pub fn paint(params: &str, name: &str) -> String {
+    format!("\x1b[{params}m{name}\x1b[0m")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeItem {
+        kind: FileKind,
+        mode: u32,
+    }
+
+    impl Listable for FakeItem {
+        fn name(&self) -> String {
+            String::new()
+        }
+        fn len(&self) -> u64 {
+            0
+        }
+        fn mtime_secs(&self) -> u64 {
+            0
+        }
+        fn mode(&self) -> u32 {
+            self.mode
+        }
+        fn is_dir(&self) -> bool {
+            self.kind == FileKind::Directory
+        }
+        fn is_symlink(&self) -> bool {
+            self.kind == FileKind::Symlink
+        }
+        fn uid(&self) -> u32 {
+            0
+        }
+        fn gid(&self) -> u32 {
+            0
+        }
+        fn file_kind(&self) -> FileKind {
+            self.kind
+        }
+    }
+
+    fn file(mode: u32) -> FakeItem {
+        FakeItem {
+            kind: FileKind::File,
+            mode,
+        }
+    }
+
+    #[test]
+    fn dotless_glob_matches_on_full_name_suffix() {
+        let colors = LsColors::parse("*~=00;90:*#=00;91");
+        assert_eq!(colors.style_for(&file(0o644), "backup~"), Some("00;90"));
+        assert_eq!(colors.style_for(&file(0o644), "#scratch#"), Some("00;91"));
+        assert_eq!(colors.style_for(&file(0o644), "plain"), None);
+    }
+
+    #[test]
+    fn dot_extension_still_takes_priority_over_type() {
+        let colors = LsColors::parse("*.tar=01;31:di=01;34");
+        assert_eq!(
+            colors.style_for(
+                &FakeItem {
+                    kind: FileKind::Directory,
+                    mode: 0o755
+                },
+                "archive.tar"
+            ),
+            Some("01;31")
+        );
+    }
+
+    #[test]
+    fn device_and_ipc_type_keys_are_reachable() {
+        let colors = LsColors::parse("pi=01;33:so=01;35:bd=01;36:cd=01;37");
+        let cases = [
+            (FileKind::Fifo, "01;33"),
+            (FileKind::Socket, "01;35"),
+            (FileKind::BlockDevice, "01;36"),
+            (FileKind::CharDevice, "01;37"),
+        ];
+        for (kind, expected) in cases {
+            let item = FakeItem { kind, mode: 0o644 };
+            assert_eq!(colors.style_for(&item, "name"), Some(expected));
+        }
+    }
+
+    #[test]
+    fn executable_regular_file_falls_back_to_ex() {
+        let colors = LsColors::parse("ex=01;32");
+        assert_eq!(colors.style_for(&file(0o755), "run"), Some("01;32"));
+        assert_eq!(colors.style_for(&file(0o644), "run"), None);
+    }
+
+    #[test]
+    fn lscolors_decodes_bold_foreground_and_background_letters() {
+        // Directory slot: bold blue (`E`) on default background.
+        let colors = LsColors::parse_lscolors("Exfxcxdxbxegedabagacad");
+        assert_eq!(
+            colors.style_for(
+                &FakeItem {
+                    kind: FileKind::Directory,
+                    mode: 0o755
+                },
+                "dir"
+            ),
+            Some("01;34")
+        );
+        // Block-device slot: plain blue (`e`) on cyan (`g`).
+        assert_eq!(
+            colors.style_for(
+                &FakeItem {
+                    kind: FileKind::BlockDevice,
+                    mode: 0o644
+                },
+                "dev"
+            ),
+            Some("34;46")
+        );
+    }
+
+    #[test]
+    fn lscolors_default_slot_falls_back_to_the_built_in_scheme() {
+        // All-`x` spec overrides nothing, so di/ln/ex keep their defaults.
+        let colors = LsColors::parse_lscolors("xxxxxxxxxxxxxxxxxxxxxx");
+        assert_eq!(
+            colors.style_for(
+                &FakeItem {
+                    kind: FileKind::Directory,
+                    mode: 0o755
+                },
+                "dir"
+            ),
+            Some(DEFAULT_DIR)
+        );
+    }
+}
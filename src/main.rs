@@ -2,14 +2,31 @@ use atty::Stream;
 use chrono::{DateTime, Local};
 use clap::{Parser, ValueEnum, ArgAction};
 use humansize::{format_size, DECIMAL};
-use owo_colors::OwoColorize;
 use std::cmp::Ordering;
 use std::ffi::OsString;
 use std::fs::{self, DirEntry, Metadata};
 use std::io;
+use owo_colors::OwoColorize;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+mod archive;
+mod dirsize;
+mod git_status;
+mod grid;
+mod listable;
+mod ls_colors;
+mod owner;
+mod quoting;
+mod tree;
+use dirsize::DirSizeCache;
+use git_status::GitContext;
+use grid::Cell;
+use listable::Listable;
+use ls_colors::LsColors;
+use owner::OwnerCache;
+use quoting::QuotingStyle;
+
 #[derive(Parser, Debug)]
 #[command(
     name = "ruls",
@@ -17,48 +34,94 @@ use walkdir::WalkDir;
     about = "Rust ls clone (common flags)",
     disable_help_flag = true
 )]
-struct Args {
+pub(crate) struct Args {
     #[arg(long = "help", action = ArgAction::Help)]
     _help: Option<bool>,   // WICHTIG: Option<bool> oder kein normales bool
 
     #[arg(short = 'a', long = "all")]
-    all: bool,
+    pub(crate) all: bool,
 
     #[arg(short = 'A', long = "almost-all")]
-    almost_all: bool,
+    pub(crate) almost_all: bool,
 
     #[arg(short = 'l', long = "long")]
-    long: bool,
+    pub(crate) long: bool,
 
     #[arg(short = 'h', long = "human-readable")]
-    human_readable: bool,
+    pub(crate) human_readable: bool,
 
     #[arg(short = 'R', long = "recursive")]
-    recursive: bool,
+    pub(crate) recursive: bool,
 
     #[arg(short = 'r', long = "reverse")]
-    reverse: bool,
+    pub(crate) reverse: bool,
 
     #[arg(short = 't', long = "time")]
-    sort_time: bool,
+    pub(crate) sort_time: bool,
 
     #[arg(short = 'S', long = "size")]
-    sort_size: bool,
+    pub(crate) sort_size: bool,
 
     #[arg(short = '1', long = "one-per-line")]
-    one_per_line: bool,
+    pub(crate) one_per_line: bool,
 
     #[arg(short = 'F', long = "classify")]
-    classify: bool,
+    pub(crate) classify: bool,
 
     #[arg(long = "dirs-first")]
-    dirs_first: bool,
+    pub(crate) dirs_first: bool,
+
+    #[arg(short = 'C', long = "columns")]
+    pub(crate) columns: bool,
+
+    #[arg(short = 'x')]
+    pub(crate) row_major: bool,
 
     #[arg(long = "color", value_enum, default_value_t = ColorWhen::Auto)]
-    color: ColorWhen,
+    pub(crate) color: ColorWhen,
+
+    #[arg(short = 'n', long = "numeric-uid-gid")]
+    pub(crate) numeric_uid_gid: bool,
+
+    #[arg(long = "git")]
+    pub(crate) git: bool,
+
+    #[arg(long = "archive")]
+    pub(crate) archive: bool,
+
+    /// Render a recursive tree instead of per-directory listings.
+    #[arg(long = "tree")]
+    pub(crate) tree: bool,
+
+    /// Show recursive directory totals (`du`-style) instead of a directory
+    /// entry's raw metadata size.
+    #[arg(short = 's', long = "total-size")]
+    pub(crate) total_size: bool,
+
+    /// How to quote file names; defaults to `shell` on a tty and `literal`
+    /// otherwise.
+    #[arg(long = "quoting-style", value_enum)]
+    pub(crate) quoting_style: Option<QuotingStyle>,
+
+    /// Shortcut for `--quoting-style=c`.
+    #[arg(short = 'Q', long = "quote-name")]
+    pub(crate) quote_name: bool,
 
     #[arg(value_name = "PATH", default_value = ".", num_args = 0..)]
-    paths: Vec<std::path::PathBuf>,
+    pub(crate) paths: Vec<std::path::PathBuf>,
+}
+
+/// Shared, read-only state threaded through the listing/formatting
+/// functions: parsed args plus the caches and settings that depend on the
+/// environment rather than on any one `Item`.
+pub(crate) struct Ctx<'a> {
+    pub(crate) args: &'a Args,
+    pub(crate) use_color: bool,
+    pub(crate) colors: &'a LsColors,
+    pub(crate) owners: &'a OwnerCache,
+    pub(crate) git: &'a GitContext,
+    pub(crate) dir_sizes: &'a DirSizeCache,
+    pub(crate) quoting: QuotingStyle,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -69,12 +132,120 @@ enum ColorWhen {
 }
 
 #[derive(Debug, Clone)]
-struct Item {
-    path: PathBuf,
+pub(crate) struct Item {
+    pub(crate) path: PathBuf,
     file_name: OsString,
-    meta: Metadata,
+    pub(crate) meta: Metadata,
     // For symlinks we keep extra info; on many platforms meta follows symlink vs link differs.
-    is_symlink: bool,
+    pub(crate) is_symlink: bool,
+}
+
+impl Listable for Item {
+    fn name(&self) -> String {
+        self.file_name.to_string_lossy().into_owned()
+    }
+
+    fn len(&self) -> u64 {
+        self.meta.len()
+    }
+
+    fn mtime_secs(&self) -> u64 {
+        mtime(&self.meta)
+    }
+
+    fn mode(&self) -> u32 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            self.meta.permissions().mode() & 0o7777
+        }
+        #[cfg(not(unix))]
+        {
+            0
+        }
+    }
+
+    fn is_dir(&self) -> bool {
+        self.meta.is_dir()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    fn uid(&self) -> u32 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            self.meta.uid()
+        }
+        #[cfg(not(unix))]
+        {
+            0
+        }
+    }
+
+    fn gid(&self) -> u32 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            self.meta.gid()
+        }
+        #[cfg(not(unix))]
+        {
+            0
+        }
+    }
+
+    fn nlink(&self) -> u64 {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            self.meta.nlink()
+        }
+        #[cfg(not(unix))]
+        {
+            1
+        }
+    }
+
+    fn symlink_target(&self) -> Option<String> {
+        if self.is_symlink {
+            fs::read_link(&self.path)
+                .ok()
+                .map(|t| t.display().to_string())
+        } else {
+            None
+        }
+    }
+
+    fn real_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn file_kind(&self) -> listable::FileKind {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            let ft = self.meta.file_type();
+            if ft.is_fifo() {
+                return listable::FileKind::Fifo;
+            } else if ft.is_socket() {
+                return listable::FileKind::Socket;
+            } else if ft.is_block_device() {
+                return listable::FileKind::BlockDevice;
+            } else if ft.is_char_device() {
+                return listable::FileKind::CharDevice;
+            }
+        }
+        if self.is_symlink {
+            listable::FileKind::Symlink
+        } else if self.meta.is_dir() {
+            listable::FileKind::Directory
+        } else {
+            listable::FileKind::File
+        }
+    }
 }
 
 fn main() -> io::Result<()> {
@@ -86,6 +257,31 @@ fn main() -> io::Result<()> {
         ColorWhen::Auto => atty::is(Stream::Stdout),
     };
 
+    let colors = LsColors::from_env();
+    let owners = OwnerCache::new();
+    let git = GitContext::new();
+    let dir_sizes = DirSizeCache::new();
+    let quoting = if args.quote_name {
+        QuotingStyle::C
+    } else {
+        args.quoting_style.unwrap_or_else(|| {
+            if atty::is(Stream::Stdout) {
+                QuotingStyle::Shell
+            } else {
+                QuotingStyle::Literal
+            }
+        })
+    };
+    let ctx = Ctx {
+        args: &args,
+        use_color,
+        colors: &colors,
+        owners: &owners,
+        git: &git,
+        dir_sizes: &dir_sizes,
+        quoting,
+    };
+
     let multiple = args.paths.len() > 1;
 
     for (i, p) in args.paths.iter().enumerate() {
@@ -97,22 +293,27 @@ fn main() -> io::Result<()> {
             println!("{}:", p.display());
         }
 
-        if args.recursive {
-            list_recursive(p, &args, use_color)?;
+        if args.tree {
+            tree::print_tree(p, &ctx)?;
+        } else if args.recursive {
+            list_recursive(p, &ctx)?;
         } else {
-            list_single_dir_or_file(p, &args, use_color)?;
+            list_single_dir_or_file(p, &ctx)?;
         }
     }
 
     Ok(())
 }
 
-fn list_recursive(path: &Path, args: &Args, use_color: bool) -> io::Result<()> {
-    // If path is file -> just print it
+fn list_recursive(path: &Path, ctx: &Ctx) -> io::Result<()> {
+    // If path is file -> just print it (or browse it, for a tar archive).
     if let Ok(m) = fs::symlink_metadata(path) {
         if !m.is_dir() {
+            if ctx.args.archive && archive::is_tar_path(path) {
+                return print_items(&archive::list_archive(path)?, ctx, None);
+            }
             let item = mk_item_from_path(path.to_path_buf(), &m)?;
-            print_items(&[item], args, use_color, None)?;
+            print_items(&[Box::new(item) as Box<dyn Listable>], ctx, None)?;
             return Ok(());
         }
     }
@@ -133,39 +334,45 @@ fn list_recursive(path: &Path, args: &Args, use_color: bool) -> io::Result<()> {
         first_dir = false;
 
         println!("{}:", dir_path.display());
-        list_dir(&dir_path, args, use_color)?;
+        list_dir(&dir_path, ctx)?;
     }
     Ok(())
 }
 
-fn list_single_dir_or_file(path: &Path, args: &Args, use_color: bool) -> io::Result<()> {
+fn list_single_dir_or_file(path: &Path, ctx: &Ctx) -> io::Result<()> {
     let meta = fs::symlink_metadata(path)?;
     if meta.is_dir() {
-        list_dir(path, args, use_color)
+        list_dir(path, ctx)
+    } else if ctx.args.archive && archive::is_tar_path(path) {
+        print_items(&archive::list_archive(path)?, ctx, None)
     } else {
         let item = mk_item_from_path(path.to_path_buf(), &meta)?;
-        print_items(&[item], args, use_color, None)
+        print_items(&[Box::new(item) as Box<dyn Listable>], ctx, None)
     }
 }
 
-fn list_dir(path: &Path, args: &Args, use_color: bool) -> io::Result<()> {
+fn list_dir(path: &Path, ctx: &Ctx) -> io::Result<()> {
     let mut items = Vec::new();
 
     for entry in fs::read_dir(path)? {
         let entry = entry?;
-        if !should_include(&entry, args) {
+        if !should_include(&entry, ctx.args) {
             continue;
         }
         let item = mk_item_from_entry(entry)?;
         items.push(item);
     }
 
-    sort_items(&mut items, args);
-    print_items(&items, args, use_color, Some(path))?;
+    sort_items(&mut items, ctx);
+    let items: Vec<Box<dyn Listable>> = items
+        .into_iter()
+        .map(|it| Box::new(it) as Box<dyn Listable>)
+        .collect();
+    print_items(&items, ctx, Some(path))?;
     Ok(())
 }
 
-fn should_include(entry: &DirEntry, args: &Args) -> bool {
+pub(crate) fn should_include(entry: &DirEntry, args: &Args) -> bool {
     let name = entry.file_name();
     let name = name.to_string_lossy();
 
@@ -185,7 +392,7 @@ fn should_include(entry: &DirEntry, args: &Args) -> bool {
     false
 }
 
-fn mk_item_from_entry(entry: DirEntry) -> io::Result<Item> {
+pub(crate) fn mk_item_from_entry(entry: DirEntry) -> io::Result<Item> {
     // symlink_metadata doesn't follow symlink
     let meta = fs::symlink_metadata(entry.path())?;
     let is_symlink = meta.file_type().is_symlink();
@@ -210,19 +417,19 @@ fn mk_item_from_path(path: PathBuf, meta: &Metadata) -> io::Result<Item> {
     })
 }
 
-fn sort_items(items: &mut [Item], args: &Args) {
-    items.sort_by(|a, b| compare_items(a, b, args));
-    if args.reverse {
+pub(crate) fn sort_items(items: &mut [Item], ctx: &Ctx) {
+    items.sort_by(|a, b| compare_items(a, b, ctx));
+    if ctx.args.reverse {
         items.reverse();
     }
 }
 
-fn compare_items(a: &Item, b: &Item, args: &Args) -> Ordering {
+fn compare_items(a: &Item, b: &Item, ctx: &Ctx) -> Ordering {
+    let args = ctx.args;
+
     // dirs-first (optional primary key)
     if args.dirs_first {
-        let ad = a.meta.is_dir();
-        let bd = b.meta.is_dir();
-        match (ad, bd) {
+        match (a.meta.is_dir(), b.meta.is_dir()) {
             (true, false) => return Ordering::Less,
             (false, true) => return Ordering::Greater,
             _ => {}
@@ -230,8 +437,8 @@ fn compare_items(a: &Item, b: &Item, args: &Args) -> Ordering {
     }
 
     if args.sort_size {
-        let sa = a.meta.len();
-        let sb = b.meta.len();
+        let sa = sort_len(a, ctx);
+        let sb = sort_len(b, ctx);
         match sb.cmp(&sa) {
             Ordering::Equal => {}
             ord => return ord,
@@ -249,35 +456,27 @@ fn compare_items(a: &Item, b: &Item, args: &Args) -> Ordering {
     a.file_name.cmp(&b.file_name)
 }
 
-fn print_items(items: &[Item], args: &Args, use_color: bool, base_dir: Option<&Path>) -> io::Result<()> {
+fn print_items(items: &[Box<dyn Listable>], ctx: &Ctx, base_dir: Option<&Path>) -> io::Result<()> {
     if items.is_empty() {
         return Ok(());
     }
 
-    if args.long {
+    if ctx.args.long {
         for it in items {
-            let line = format_long(it, args, use_color)?;
+            let line = format_long(it.as_ref(), ctx)?;
             println!("{line}");
         }
         return Ok(());
     }
 
-    // Simple mode: one-per-line vs space-separated (basic)
-    if args.one_per_line {
+    // Simple mode: one-per-line vs a terminal-width-aware grid.
+    if ctx.args.one_per_line && !ctx.args.columns {
         for it in items {
-            println!("{}", format_name(it, args, use_color)?);
+            println!("{}", format_name(it.as_ref(), ctx));
         }
     } else {
-        // Minimal "columns": just join by two spaces (not terminal-width aware).
-        let mut first = true;
-        for it in items {
-            if !first {
-                print!("  ");
-            }
-            first = false;
-            print!("{}", format_name(it, args, use_color)?);
-        }
-        println!();
+        let cells: Vec<Cell> = items.iter().map(|it| format_name_cell(it.as_ref(), ctx)).collect();
+        grid::print_grid(&cells, grid::terminal_width(), ctx.args.row_major);
     }
 
     // base_dir unused now but kept for easy extension (relative path printing, etc.)
@@ -285,87 +484,162 @@ fn print_items(items: &[Item], args: &Args, use_color: bool, base_dir: Option<&P
     Ok(())
 }
 
-fn format_long(it: &Item, args: &Args, use_color: bool) -> io::Result<String> {
-    let perms = format_permissions(&it.meta);
-    let nlink = format_nlink(&it.meta);
-    let owner = format_owner(&it.meta);
-    let group = format_group(&it.meta);
-    let size = format_size_field(it, args);
-    let time = format_mtime(&it.meta);
-    let name = format_name(it, args, use_color)?;
-
-    // symlink target (unix-ish behavior)
-    let link_part = if it.is_symlink {
-        match fs::read_link(&it.path) {
-            Ok(target) => format!(" -> {}", target.display()),
-            Err(_) => String::new(),
+fn format_long(it: &dyn Listable, ctx: &Ctx) -> io::Result<String> {
+    let perms = format_permissions(it);
+    let nlink = it.nlink();
+    let owner = format_owner(it, ctx);
+    let group = format_group(it, ctx);
+    let size = format_size_field(it, ctx);
+    let time = format_mtime(it.mtime_secs());
+    let name = format_name(it, ctx);
+    let git = format_git_status(it, ctx);
+
+    let link_part = match it.symlink_target() {
+        Some(target) => format!(" -> {target}"),
+        None => String::new(),
+    };
+
+    Ok(match git {
+        Some(git) => {
+            format!("{perms} {git} {nlink:>2} {owner:<8} {group:<8} {size:>8} {time} {name}{link_part}")
         }
+        None => format!("{perms} {nlink:>2} {owner:<8} {group:<8} {size:>8} {time} {name}{link_part}"),
+    })
+}
+
+/// Two-character git status column (e.g. `M-`), shown in every long listing
+/// whether or not `--git` was given; `None` when neither `--git` nor `-l` is
+/// set or `it` isn't inside a git working tree (always true for archive
+/// members, which have no real path).
+fn format_git_status(it: &dyn Listable, ctx: &Ctx) -> Option<String> {
+    if !(ctx.args.git || ctx.args.long) {
+        return None;
+    }
+
+    let (index, worktree) = ctx.git.status_for(it.real_path()?)?;
+    if !ctx.use_color {
+        return Some(format!("{index}{worktree}"));
+    }
+
+    let index = if index == '-' {
+        index.to_string()
     } else {
-        String::new()
+        index.to_string().green().to_string()
     };
-
-    Ok(format!("{perms} {nlink:>2} {owner:<8} {group:<8} {size:>8} {time} {name}{link_part}"))
+    let worktree = if worktree == '-' {
+        worktree.to_string()
+    } else {
+        worktree.to_string().red().to_string()
+    };
+    Some(format!("{index}{worktree}"))
 }
 
-fn format_name(it: &Item, args: &Args, use_color: bool) -> io::Result<String> {
-    let base = it.file_name.to_string_lossy().to_string();
-    let mut s = if use_color {
-        colorize_name(it, &base)
+pub(crate) fn format_name(it: &dyn Listable, ctx: &Ctx) -> String {
+    let raw = it.name();
+    let quoted = quoting::quote(&raw, ctx.quoting);
+    let mut s = if ctx.use_color {
+        colorize_name(it, &raw, &quoted, ctx.colors)
     } else {
-        base
+        quoted
     };
 
-    if args.classify {
+    if ctx.args.classify {
         s.push_str(classify_suffix(it));
     }
 
-    Ok(s)
+    s
+}
+
+fn format_name_cell(it: &dyn Listable, ctx: &Ctx) -> Cell {
+    let raw = it.name();
+    let quoted = quoting::quote(&raw, ctx.quoting);
+
+    let mut plain = quoted.clone();
+    if ctx.args.classify {
+        plain.push_str(classify_suffix(it));
+    }
+
+    let styled = if ctx.use_color {
+        let mut s = colorize_name(it, &raw, &quoted, ctx.colors);
+        if ctx.args.classify {
+            s.push_str(classify_suffix(it));
+        }
+        s
+    } else {
+        plain.clone()
+    };
+
+    Cell::new(styled, &plain)
 }
 
-fn classify_suffix(it: &Item) -> &'static str {
+fn classify_suffix(it: &dyn Listable) -> &'static str {
     // Match common ls -F indicators
-    if it.meta.is_dir() {
+    if it.is_dir() {
         "/"
-    } else if it.is_symlink {
+    } else if it.is_symlink() {
         "@"
-    } else if is_executable(&it.meta) {
+    } else if is_executable_bits(it.mode(), it.is_dir()) {
         "*"
     } else {
         ""
     }
 }
 
-fn colorize_name(it: &Item, name: &str) -> String {
-    // Basic scheme:
-    // - dirs: blue
-    // - symlinks: cyan
-    // - executables: green
-    // - others: default
-    if it.meta.is_dir() {
-        name.blue().to_string()
-    } else if it.is_symlink {
-        name.cyan().to_string()
-    } else if is_executable(&it.meta) {
-        name.green().to_string()
-    } else {
-        name.to_string()
+/// Color `quoted_name` per `it`'s type/extension, matching extensions
+/// against `raw_name` (the unquoted form) so quoting can't shift which
+/// suffix `style_for` sees.
+fn colorize_name(it: &dyn Listable, raw_name: &str, quoted_name: &str, colors: &LsColors) -> String {
+    match colors.style_for(it, raw_name) {
+        Some(params) => ls_colors::paint(params, quoted_name),
+        None => quoted_name.to_string(),
     }
 }
 
-fn format_size_field(it: &Item, args: &Args) -> String {
-    if args.human_readable {
-        format_size(it.meta.len(), DECIMAL)
+pub(crate) fn format_size_field(it: &dyn Listable, ctx: &Ctx) -> String {
+    let len = effective_len(it, ctx);
+    if ctx.args.human_readable {
+        format_size(len, DECIMAL)
     } else {
-        it.meta.len().to_string()
+        len.to_string()
     }
 }
 
-fn format_mtime(meta: &Metadata) -> String {
-    let dt: DateTime<Local> = meta
-        .modified()
-        .ok()
-        .and_then(|st| DateTime::<Local>::from(st).into())
-        .unwrap_or_else(|| Local::now());
+/// Byte length to report for `it`: the recursive directory total when
+/// `-s` needs one and the cache has (or can compute) it, otherwise the
+/// entry's own metadata size. `-S` is a sort key, not a display flag, so
+/// it does not affect what's printed here; see `sort_len`. `--tree` also
+/// prints its sizes through this function, via `format_size_field` in
+/// `tree::print_children`.
+fn effective_len(it: &dyn Listable, ctx: &Ctx) -> u64 {
+    if it.is_dir() && ctx.args.total_size {
+        if let Some(path) = it.real_path() {
+            ctx.dir_sizes.ensure(path);
+            if let Some(total) = ctx.dir_sizes.size_of(path) {
+                return total;
+            }
+        }
+    }
+    it.len()
+}
+
+/// Byte length to compare by for `-S` sorting: the recursive directory
+/// total so directories sort by their aggregated size, independent of
+/// whether that total is also being displayed (see `effective_len`).
+fn sort_len(it: &Item, ctx: &Ctx) -> u64 {
+    if it.meta.is_dir() {
+        ctx.dir_sizes.ensure(&it.path);
+        if let Some(total) = ctx.dir_sizes.size_of(&it.path) {
+            return total;
+        }
+    }
+    it.meta.len()
+}
+
+fn format_mtime(epoch_secs: u64) -> String {
+    let dt: DateTime<Local> = std::time::UNIX_EPOCH
+        .checked_add(std::time::Duration::from_secs(epoch_secs))
+        .map(DateTime::<Local>::from)
+        .unwrap_or_else(Local::now);
 
     dt.format("%b %e %H:%M").to_string()
 }
@@ -379,79 +653,49 @@ fn mtime(meta: &Metadata) -> u64 {
         .unwrap_or(0)
 }
 
-fn format_permissions(meta: &Metadata) -> String {
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mode = meta.permissions().mode();
+fn format_permissions(it: &dyn Listable) -> String {
+    let mode = it.mode();
 
-        let file_type = if meta.is_dir() {
-            'd'
-        } else if meta.file_type().is_symlink() {
-            'l'
-        } else {
-            '-'
-        };
-
-        let bits = [
-            (mode & 0o400 != 0, 'r'),
-            (mode & 0o200 != 0, 'w'),
-            (mode & 0o100 != 0, 'x'),
-            (mode & 0o040 != 0, 'r'),
-            (mode & 0o020 != 0, 'w'),
-            (mode & 0o010 != 0, 'x'),
-            (mode & 0o004 != 0, 'r'),
-            (mode & 0o002 != 0, 'w'),
-            (mode & 0o001 != 0, 'x'),
-        ];
-
-        let mut s = String::with_capacity(10);
-        s.push(file_type);
-        for (set, ch) in bits {
-            s.push(if set { ch } else { '-' });
-        }
-        s
-    }
+    let file_type = if it.is_dir() {
+        'd'
+    } else if it.is_symlink() {
+        'l'
+    } else {
+        '-'
+    };
 
-    #[cfg(not(unix))]
-    {
-        // Fallback
-        if meta.is_dir() {
-            "d---------".to_string()
-        } else {
-            "----------".to_string()
-        }
-    }
-}
-fn is_executable(_meta: &Metadata) -> bool {
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        _meta.permissions().mode() & 0o111 != 0 && !_meta.is_dir()
-    }
-    #[cfg(not(unix))]
-    {
-        false
+    let bits = [
+        (mode & 0o400 != 0, 'r'),
+        (mode & 0o200 != 0, 'w'),
+        (mode & 0o100 != 0, 'x'),
+        (mode & 0o040 != 0, 'r'),
+        (mode & 0o020 != 0, 'w'),
+        (mode & 0o010 != 0, 'x'),
+        (mode & 0o004 != 0, 'r'),
+        (mode & 0o002 != 0, 'w'),
+        (mode & 0o001 != 0, 'x'),
+    ];
+
+    let mut s = String::with_capacity(10);
+    s.push(file_type);
+    for (set, ch) in bits {
+        s.push(if set { ch } else { '-' });
     }
+    s
 }
 
-fn format_nlink(_meta: &Metadata) -> String {
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::MetadataExt;
-        _meta.nlink().to_string()
-    }
-    #[cfg(not(unix))]
-    {
-        "1".to_string()
-    }
+pub(crate) fn is_executable_bits(mode: u32, is_dir: bool) -> bool {
+    mode & 0o111 != 0 && !is_dir
 }
 
-fn format_owner(_meta: &Metadata) -> String {
+fn format_owner(_it: &dyn Listable, _ctx: &Ctx) -> String {
     #[cfg(unix)]
     {
-        use std::os::unix::fs::MetadataExt;
-        _meta.uid().to_string()
+        if _ctx.args.numeric_uid_gid {
+            _it.uid().to_string()
+        } else {
+            _ctx.owners.user_name(_it.uid())
+        }
     }
     #[cfg(not(unix))]
     {
@@ -459,11 +703,14 @@ fn format_owner(_meta: &Metadata) -> String {
     }
 }
 
-fn format_group(_meta: &Metadata) -> String {
+fn format_group(_it: &dyn Listable, _ctx: &Ctx) -> String {
     #[cfg(unix)]
     {
-        use std::os::unix::fs::MetadataExt;
-        _meta.gid().to_string()
+        if _ctx.args.numeric_uid_gid {
+            _it.gid().to_string()
+        } else {
+            _ctx.owners.group_name(_it.gid())
+        }
     }
     #[cfg(not(unix))]
     {
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Caches uid/gid -> name lookups for the duration of one run, so listing a
+/// directory full of files owned by the same few users doesn't re-hit
+/// `getpwuid`/`getgrgid` for every entry.
+#[derive(Default)]
+pub struct OwnerCache {
+    users: RefCell<HashMap<u32, String>>,
+    groups: RefCell<HashMap<u32, String>>,
+}
+
+impl OwnerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// User name for `uid`, falling back to the numeric id when there's no
+    /// passwd entry.
+    #[cfg(unix)]
+    pub fn user_name(&self, uid: u32) -> String {
+        if let Some(name) = self.users.borrow().get(&uid) {
+            return name.clone();
+        }
+
+        let name = users::get_user_by_uid(uid)
+            .map(|u| u.name().to_string_lossy().into_owned())
+            .unwrap_or_else(|| uid.to_string());
+        self.users.borrow_mut().insert(uid, name.clone());
+        name
+    }
+
+    /// Group name for `gid`, falling back to the numeric id when there's no
+    /// group entry.
+    #[cfg(unix)]
+    pub fn group_name(&self, gid: u32) -> String {
+        if let Some(name) = self.groups.borrow().get(&gid) {
+            return name.clone();
+        }
+
+        let name = users::get_group_by_gid(gid)
+            .map(|g| g.name().to_string_lossy().into_owned())
+            .unwrap_or_else(|| gid.to_string());
+        self.groups.borrow_mut().insert(gid, name.clone());
+        name
+    }
+
+    #[cfg(not(unix))]
+    pub fn user_name(&self, _uid: u32) -> String {
+        "-".to_string()
+    }
+
+    #[cfg(not(unix))]
+    pub fn group_name(&self, _gid: u32) -> String {
+        "-".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Just below the top of the 32-bit uid/gid range, far above the ids
+    // allocated in practice, so no passwd or group entry should exist for it.
+    const UNKNOWN_ID: u32 = u32::MAX - 1;
+
+    #[test]
+    fn unknown_uid_falls_back_to_the_numeric_id() {
+        let cache = OwnerCache::new();
+        assert_eq!(cache.user_name(UNKNOWN_ID), UNKNOWN_ID.to_string());
+    }
+
+    #[test]
+    fn unknown_gid_falls_back_to_the_numeric_id() {
+        let cache = OwnerCache::new();
+        assert_eq!(cache.group_name(UNKNOWN_ID), UNKNOWN_ID.to_string());
+    }
+
+    #[test]
+    fn repeated_lookups_of_the_same_id_agree() {
+        let cache = OwnerCache::new();
+        assert_eq!(cache.user_name(UNKNOWN_ID), cache.user_name(UNKNOWN_ID));
+    }
+}
@@ -0,0 +1,146 @@
+use clap::ValueEnum;
+
+/// How to render file names that may contain spaces, control characters or
+/// shell metacharacters. Mirrors (a useful subset of) coreutils'
+/// `--quoting-style`.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum QuotingStyle {
+    /// Print the name exactly as returned by the filesystem.
+    Literal,
+    /// Single-quote the name, but only when it actually needs it.
+    Shell,
+    /// Like `shell`, but falls back to `$'...'` ANSI-C quoting for names
+    /// that contain control characters single quotes can't represent.
+    ShellEscape,
+    /// Always double-quote, C-string style.
+    C,
+}
+
+const SHELL_META: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '`', '$', '&', ';', '|', '<', '>', '(', ')', '{', '}', '[', ']',
+    '*', '?', '!', '~', '#', '^', '\\',
+];
+
+/// Quote `name` per `style`.
+pub fn quote(name: &str, style: QuotingStyle) -> String {
+    match style {
+        QuotingStyle::Literal => name.to_string(),
+        QuotingStyle::Shell => quote_shell(name),
+        QuotingStyle::ShellEscape => quote_shell_escape(name),
+        QuotingStyle::C => quote_c(name),
+    }
+}
+
+fn needs_shell_quoting(name: &str) -> bool {
+    name.is_empty() || name.chars().any(|c| c.is_control() || SHELL_META.contains(&c))
+}
+
+fn quote_shell(name: &str) -> String {
+    if !needs_shell_quoting(name) {
+        return name.to_string();
+    }
+
+    let mut s = String::with_capacity(name.len() + 2);
+    s.push('\'');
+    for c in name.chars() {
+        if c == '\'' {
+            s.push_str("'\\''");
+        } else {
+            s.push(c);
+        }
+    }
+    s.push('\'');
+    s
+}
+
+fn quote_shell_escape(name: &str) -> String {
+    if !name.chars().any(|c| c.is_control()) {
+        return quote_shell(name);
+    }
+
+    let mut s = String::with_capacity(name.len() + 3);
+    s.push_str("$'");
+    for c in name.chars() {
+        push_c_escaped(&mut s, c, '\'');
+    }
+    s.push('\'');
+    s
+}
+
+fn quote_c(name: &str) -> String {
+    let mut s = String::with_capacity(name.len() + 2);
+    s.push('"');
+    for c in name.chars() {
+        push_c_escaped(&mut s, c, '"');
+    }
+    s.push('"');
+    s
+}
+
+/// Append `c` to `out`, escaping it C-string style. `quote` is the
+/// surrounding quote character, which also needs escaping.
+fn push_c_escaped(out: &mut String, c: char, quote: char) {
+    match c {
+        '\n' => out.push_str("\\n"),
+        '\t' => out.push_str("\\t"),
+        '\\' => out.push_str("\\\\"),
+        c if c == quote => {
+            out.push('\\');
+            out.push(c);
+        }
+        c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+            out.push_str(&format!("\\{:03o}", c as u32));
+        }
+        c => out.push(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_never_quotes() {
+        assert_eq!(quote("has space", QuotingStyle::Literal), "has space");
+    }
+
+    #[test]
+    fn shell_leaves_plain_names_bare() {
+        assert_eq!(quote("readme.md", QuotingStyle::Shell), "readme.md");
+    }
+
+    #[test]
+    fn shell_quotes_names_with_metacharacters() {
+        assert_eq!(quote("has space", QuotingStyle::Shell), "'has space'");
+    }
+
+    #[test]
+    fn shell_escapes_embedded_single_quotes() {
+        assert_eq!(quote("it's", QuotingStyle::Shell), "'it'\\''s'");
+    }
+
+    #[test]
+    fn shell_escape_falls_back_to_plain_shell_quoting_without_control_chars() {
+        assert_eq!(
+            quote("has space", QuotingStyle::ShellEscape),
+            quote("has space", QuotingStyle::Shell)
+        );
+    }
+
+    #[test]
+    fn shell_escape_uses_ansi_c_quoting_for_control_chars() {
+        assert_eq!(quote("a\tb", QuotingStyle::ShellEscape), "$'a\\tb'");
+    }
+
+    #[test]
+    fn c_style_always_quotes_and_escapes_backslashes() {
+        assert_eq!(quote("plain", QuotingStyle::C), "\"plain\"");
+        assert_eq!(quote("back\\slash", QuotingStyle::C), "\"back\\\\slash\"");
+    }
+
+    #[test]
+    fn c_style_escapes_newlines_and_control_bytes() {
+        assert_eq!(quote("a\nb", QuotingStyle::C), "\"a\\nb\"");
+        assert_eq!(quote("a\x01b", QuotingStyle::C), "\"a\\001b\"");
+    }
+}
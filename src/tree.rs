@@ -0,0 +1,183 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::listable::Listable;
+use crate::{format_name, format_size_field, mk_item_from_entry, should_include, sort_items, Ctx};
+
+/// `--tree`: print `root` followed by its contents as a recursive tree,
+/// using the same name/size formatting and sort order as the regular
+/// listing.
+pub fn print_tree(root: &Path, ctx: &Ctx) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    writeln!(out, "{}", root.display())?;
+    print_children(root, ctx, "", &mut out)
+}
+
+/// Connector drawn in front of one entry's name: the last entry in a
+/// directory gets the corner, everything before it gets the tee.
+fn connector(is_last: bool) -> &'static str {
+    if is_last {
+        "└── "
+    } else {
+        "├── "
+    }
+}
+
+/// Prefix for `item`'s own children: a completed branch leaves blank space
+/// behind it, an in-progress one keeps drawing its vertical bar.
+fn child_prefix(prefix: &str, is_last: bool) -> String {
+    format!("{prefix}{}", if is_last { "    " } else { "│   " })
+}
+
+fn print_children(dir: &Path, ctx: &Ctx, prefix: &str, out: &mut dyn Write) -> io::Result<()> {
+    let mut items = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !should_include(&entry, ctx.args) {
+            continue;
+        }
+        items.push(mk_item_from_entry(entry)?);
+    }
+    sort_items(&mut items, ctx);
+
+    let last_index = items.len().saturating_sub(1);
+    for (i, item) in items.iter().enumerate() {
+        let is_last = i == last_index;
+
+        let size_part = if ctx.args.total_size {
+            format!(" [{}]", format_size_field(item, ctx))
+        } else {
+            String::new()
+        };
+        writeln!(
+            out,
+            "{prefix}{}{}{size_part}",
+            connector(is_last),
+            format_name(item, ctx)
+        )?;
+
+        if item.is_dir() {
+            print_children(&item.path, ctx, &child_prefix(prefix, is_last), out)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dirsize::DirSizeCache;
+    use crate::git_status::GitContext;
+    use crate::ls_colors::LsColors;
+    use crate::owner::OwnerCache;
+    use crate::quoting::QuotingStyle;
+    use crate::{Args, ColorWhen};
+    use std::path::PathBuf;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("ruls-tree-test-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn test_args(total_size: bool) -> Args {
+        Args {
+            _help: None,
+            all: false,
+            almost_all: false,
+            long: false,
+            human_readable: false,
+            recursive: false,
+            reverse: false,
+            sort_time: false,
+            sort_size: false,
+            one_per_line: false,
+            classify: false,
+            dirs_first: false,
+            columns: false,
+            row_major: false,
+            color: ColorWhen::Never,
+            numeric_uid_gid: false,
+            git: false,
+            archive: false,
+            tree: true,
+            total_size,
+            quoting_style: None,
+            quote_name: false,
+            paths: Vec::new(),
+        }
+    }
+
+    fn render(root: &Path, ctx: &Ctx) -> String {
+        let mut out = Vec::new();
+        print_children(root, ctx, "", &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn last_child_gets_the_corner_and_a_blank_grandchild_prefix() {
+        let tmp = TempDir::new("prefix");
+        fs::write(tmp.0.join("a.txt"), b"hello").unwrap();
+        fs::create_dir_all(tmp.0.join("sub")).unwrap();
+        fs::write(tmp.0.join("sub/b.txt"), b"hi").unwrap();
+
+        let args = test_args(false);
+        let colors = LsColors::from_env();
+        let owners = OwnerCache::new();
+        let git = GitContext::new();
+        let dir_sizes = DirSizeCache::new();
+        let ctx = Ctx {
+            args: &args,
+            use_color: false,
+            colors: &colors,
+            owners: &owners,
+            git: &git,
+            dir_sizes: &dir_sizes,
+            quoting: QuotingStyle::Literal,
+        };
+
+        // "a.txt" sorts before the "sub" directory, so it is not last and
+        // gets the tee; "sub" is last so it gets the corner and its own
+        // child starts from a blank prefix.
+        assert_eq!(
+            render(&tmp.0, &ctx),
+            "├── a.txt\n└── sub\n    └── b.txt\n"
+        );
+    }
+
+    #[test]
+    fn total_size_annotates_each_line_with_its_size() {
+        let tmp = TempDir::new("sizes");
+        fs::write(tmp.0.join("a.txt"), b"hello").unwrap();
+
+        let args = test_args(true);
+        let colors = LsColors::from_env();
+        let owners = OwnerCache::new();
+        let git = GitContext::new();
+        let dir_sizes = DirSizeCache::new();
+        let ctx = Ctx {
+            args: &args,
+            use_color: false,
+            colors: &colors,
+            owners: &owners,
+            git: &git,
+            dir_sizes: &dir_sizes,
+            quoting: QuotingStyle::Literal,
+        };
+
+        assert_eq!(render(&tmp.0, &ctx), "└── a.txt [5]\n");
+    }
+}